@@ -1,12 +1,19 @@
 // This should be included in georust/geo to avoid a newtype
 /// Geopackage conversions for [georust/geo](https://github.com/georust/geo)
 pub mod geo {
+    use crate::error::Result as GzResult;
     use crate::geo::RustGeo;
     use crate::wkb;
+    use crate::GeozeroGeometry;
     use sqlx::decode::Decode;
-    use sqlx::sqlite::{Sqlite, SqliteTypeInfo, SqliteValue};
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
+    use std::borrow::Cow;
 
-    pub struct Geometry(pub geo_types::Geometry<f64>);
+    /// A `geo_types` geometry read from (or destined for) a GeoPackage BLOB
+    /// column, together with the SRID from its GeoPackage header, if any.
+    pub struct Geometry(pub geo_types::Geometry<f64>, pub Option<i32>);
 
     impl sqlx::Type<Sqlite> for Geometry {
         fn type_info() -> SqliteTypeInfo {
@@ -18,12 +25,69 @@ pub mod geo {
         fn decode(value: SqliteValue<'de>) -> sqlx::Result<Self> {
             let mut blob = <&[u8] as Decode<Sqlite>>::decode(value)?;
             let mut geo = RustGeo::new();
-            wkb::process_gpkg_geom(&mut blob, &mut geo)
+            let srid = wkb::process_gpkg_geom(&mut blob, &mut geo)
                 .map_err(|e| sqlx::Error::Decode(e.to_string().into()))?;
-            let geom = Geometry {
-                0: geo.geometry().to_owned(),
-            };
-            Ok(geom)
+            Ok(Geometry(geo.geometry().to_owned(), srid))
+        }
+    }
+
+    /// Encode `geom` (with optional `srid`) as a GeoPackage geometry BLOB,
+    /// using the [`wkb::WkbWriter`] GeoPackage dialect for the header.
+    fn to_gpkg_blob(geom: &geo_types::Geometry<f64>, srid: Option<i32>) -> GzResult<Vec<u8>> {
+        let mut blob: Vec<u8> = Vec::new();
+        let mut writer = wkb::WkbWriter::with_dialect(&mut blob, wkb::WkbDialect::Geopackage);
+        if let Some(srid) = srid {
+            writer = writer.with_srid(srid);
+        }
+        geom.process_geom(&mut writer)?;
+        Ok(blob)
+    }
+
+    impl<'q> Encode<'q, Sqlite> for Geometry {
+        fn encode_by_ref(
+            &self,
+            buf: &mut Vec<SqliteArgumentValue<'q>>,
+        ) -> Result<IsNull, BoxDynError> {
+            let blob = to_gpkg_blob(&self.0, self.1)?;
+            buf.push(SqliteArgumentValue::Blob(Cow::Owned(blob)));
+            Ok(IsNull::No)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use geo_types::{line_string, Geometry as GeoGeometry, LineString, MultiPoint};
+
+        fn roundtrip(geom: GeoGeometry<f64>, srid: Option<i32>) -> (GeoGeometry<f64>, Option<i32>) {
+            let blob = to_gpkg_blob(&geom, srid).unwrap();
+            let mut reader = blob.as_slice();
+            let mut geo = RustGeo::new();
+            let srid = wkb::process_gpkg_geom(&mut reader, &mut geo).unwrap();
+            (geo.geometry().to_owned(), srid)
+        }
+
+        #[test]
+        fn roundtrips_a_linestring_with_srid() {
+            let geom: GeoGeometry<f64> = line_string![(x: 0., y: 0.), (x: 1., y: 2.)].into();
+            let (out, srid) = roundtrip(geom.clone(), Some(4326));
+            assert_eq!(out, geom);
+            assert_eq!(srid, Some(4326));
+        }
+
+        #[test]
+        fn roundtrips_an_empty_geometry() {
+            let geom: GeoGeometry<f64> = MultiPoint::<f64>(vec![]).into();
+            let (out, srid) = roundtrip(geom.clone(), None);
+            assert_eq!(out, geom);
+            assert_eq!(srid, None);
+        }
+
+        #[test]
+        fn roundtrips_an_empty_linestring() {
+            let geom: GeoGeometry<f64> = LineString::<f64>(vec![]).into();
+            let (out, _) = roundtrip(geom.clone(), None);
+            assert_eq!(out, geom);
         }
     }
 }
@@ -34,10 +98,16 @@ pub mod geo {
 pub mod geos {
     use crate::geos::Geos;
     use crate::wkb;
+    use crate::GeozeroGeometry;
     use sqlx::decode::Decode;
-    use sqlx::sqlite::{Sqlite, SqliteTypeInfo, SqliteValue};
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
+    use std::borrow::Cow;
 
-    pub struct Geometry<'a>(pub geos::Geometry<'a>);
+    /// A GEOS geometry read from (or destined for) a GeoPackage BLOB column,
+    /// together with the SRID from its GeoPackage header, if any.
+    pub struct Geometry<'a>(pub geos::Geometry<'a>, pub Option<i32>);
 
     impl sqlx::Type<Sqlite> for Geometry<'_> {
         fn type_info() -> SqliteTypeInfo {
@@ -49,12 +119,25 @@ pub mod geos {
         fn decode(value: SqliteValue<'de>) -> sqlx::Result<Self> {
             let mut blob = <&[u8] as Decode<Sqlite>>::decode(value)?;
             let mut geo = Geos::new();
-            wkb::process_gpkg_geom(&mut blob, &mut geo)
+            let srid = wkb::process_gpkg_geom(&mut blob, &mut geo)
                 .map_err(|e| sqlx::Error::Decode(e.to_string().into()))?;
-            let geom = Geometry {
-                0: geo.geometry().to_owned(),
-            };
-            Ok(geom)
+            Ok(Geometry(geo.geometry().to_owned(), srid))
         }
     }
-}
\ No newline at end of file
+
+    impl<'q> Encode<'q, Sqlite> for Geometry<'_> {
+        fn encode_by_ref(
+            &self,
+            buf: &mut Vec<SqliteArgumentValue<'q>>,
+        ) -> Result<IsNull, BoxDynError> {
+            let mut blob: Vec<u8> = Vec::new();
+            let mut writer = wkb::WkbWriter::with_dialect(&mut blob, wkb::WkbDialect::Geopackage);
+            if let Some(srid) = self.1 {
+                writer = writer.with_srid(srid);
+            }
+            self.0.process_geom(&mut writer)?;
+            buf.push(SqliteArgumentValue::Blob(Cow::Owned(blob)));
+            Ok(IsNull::No)
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub use geozero::error;
+pub use geozero::{ColumnValue, GeomProcessor, GeozeroDatasource, GeozeroGeometry};
+
+pub mod geopackage;
+pub mod postgis;
+pub mod wkb;
@@ -0,0 +1,144 @@
+// This should be included in georust/geo to avoid a newtype
+/// PostGIS (EWKB) conversions for [georust/geo](https://github.com/georust/geo)
+pub mod geo {
+    use crate::error::Result as GzResult;
+    use crate::geo::RustGeo;
+    use crate::wkb;
+    use crate::GeozeroGeometry;
+    use sqlx::decode::Decode;
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres};
+
+    /// A `geo_types` geometry read from (or destined for) a PostGIS EWKB
+    /// column, together with its SRID, if any.
+    pub struct Geometry(pub geo_types::Geometry<f64>, pub Option<i32>);
+
+    impl sqlx::Type<Postgres> for Geometry {
+        fn type_info() -> PgTypeInfo {
+            PgTypeInfo::with_name("geometry")
+        }
+    }
+
+    impl<'de> Decode<'de, Postgres> for Geometry {
+        fn decode(value: PgValueRef<'de>) -> sqlx::Result<Self> {
+            let mut blob = <&[u8] as Decode<Postgres>>::decode(value)?;
+            let mut geo = RustGeo::new();
+            let srid = wkb::process_ewkb_geom(&mut blob, &mut geo)
+                .map_err(|e| sqlx::Error::Decode(e.to_string().into()))?;
+            Ok(Geometry(geo.geometry().to_owned(), srid))
+        }
+    }
+
+    /// Encode `geom` (with optional `srid`) as EWKB, using the
+    /// [`wkb::WkbWriter`] EWKB dialect.
+    fn to_ewkb(geom: &geo_types::Geometry<f64>, srid: Option<i32>) -> GzResult<Vec<u8>> {
+        let mut ewkb: Vec<u8> = Vec::new();
+        let mut writer = wkb::WkbWriter::with_dialect(&mut ewkb, wkb::WkbDialect::Ewkb);
+        if let Some(srid) = srid {
+            writer = writer.with_srid(srid);
+        }
+        geom.process_geom(&mut writer)?;
+        Ok(ewkb)
+    }
+
+    impl<'q> Encode<'q, Postgres> for Geometry {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            let mut writer = wkb::WkbWriter::with_dialect(buf, wkb::WkbDialect::Ewkb);
+            if let Some(srid) = self.1 {
+                writer = writer.with_srid(srid);
+            }
+            self.0.process_geom(&mut writer)?;
+            Ok(IsNull::No)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use geo_types::{line_string, Geometry as GeoGeometry, LineString, MultiPoint};
+
+        fn roundtrip(geom: GeoGeometry<f64>, srid: Option<i32>) -> (GeoGeometry<f64>, Option<i32>) {
+            let ewkb = to_ewkb(&geom, srid).unwrap();
+            let mut reader = ewkb.as_slice();
+            let mut geo = RustGeo::new();
+            let srid = wkb::process_ewkb_geom(&mut reader, &mut geo).unwrap();
+            (geo.geometry().to_owned(), srid)
+        }
+
+        #[test]
+        fn roundtrips_a_linestring_with_srid() {
+            let geom: GeoGeometry<f64> = line_string![(x: 0., y: 0.), (x: 1., y: 2.)].into();
+            let (out, srid) = roundtrip(geom.clone(), Some(4326));
+            assert_eq!(out, geom);
+            assert_eq!(srid, Some(4326));
+        }
+
+        #[test]
+        fn roundtrips_a_geometry_without_srid() {
+            let geom: GeoGeometry<f64> = line_string![(x: 0., y: 0.), (x: 1., y: 2.)].into();
+            let (out, srid) = roundtrip(geom.clone(), None);
+            assert_eq!(out, geom);
+            assert_eq!(srid, None);
+        }
+
+        #[test]
+        fn roundtrips_an_empty_geometry() {
+            let geom: GeoGeometry<f64> = MultiPoint::<f64>(vec![]).into();
+            let (out, srid) = roundtrip(geom.clone(), None);
+            assert_eq!(out, geom);
+            assert_eq!(srid, None);
+        }
+
+        #[test]
+        fn roundtrips_an_empty_linestring() {
+            let geom: GeoGeometry<f64> = LineString::<f64>(vec![]).into();
+            let (out, _) = roundtrip(geom.clone(), None);
+            assert_eq!(out, geom);
+        }
+    }
+}
+
+// This should be included in georust/geos to avoid a newtype
+/// PostGIS (EWKB) conversions for [GEOS](https://github.com/georust/geos)
+#[cfg(feature = "geos-lib")]
+pub mod geos {
+    use crate::geos::Geos;
+    use crate::wkb;
+    use crate::GeozeroGeometry;
+    use sqlx::decode::Decode;
+    use sqlx::encode::{Encode, IsNull};
+    use sqlx::error::BoxDynError;
+    use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres};
+
+    /// A GEOS geometry read from (or destined for) a PostGIS EWKB column,
+    /// together with its SRID, if any.
+    pub struct Geometry<'a>(pub geos::Geometry<'a>, pub Option<i32>);
+
+    impl sqlx::Type<Postgres> for Geometry<'_> {
+        fn type_info() -> PgTypeInfo {
+            PgTypeInfo::with_name("geometry")
+        }
+    }
+
+    impl<'de> Decode<'de, Postgres> for Geometry<'static> {
+        fn decode(value: PgValueRef<'de>) -> sqlx::Result<Self> {
+            let mut blob = <&[u8] as Decode<Postgres>>::decode(value)?;
+            let mut geo = Geos::new();
+            let srid = wkb::process_ewkb_geom(&mut blob, &mut geo)
+                .map_err(|e| sqlx::Error::Decode(e.to_string().into()))?;
+            Ok(Geometry(geo.geometry().to_owned(), srid))
+        }
+    }
+
+    impl<'q> Encode<'q, Postgres> for Geometry<'_> {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            let mut writer = wkb::WkbWriter::with_dialect(buf, wkb::WkbDialect::Ewkb);
+            if let Some(srid) = self.1 {
+                writer = writer.with_srid(srid);
+            }
+            self.0.process_geom(&mut writer)?;
+            Ok(IsNull::No)
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Well-Known Binary (WKB) reader/writer, including the GeoPackage and
+//! PostGIS/EWKB dialect framing used by [`crate::geopackage`] and
+//! [`crate::postgis`].
+use crate::error::{GeozeroError, Result};
+use crate::GeomProcessor;
+use std::io::{Read, Write};
+
+const WKB_POINT: u32 = 1;
+const WKB_LINESTRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOINT: u32 = 4;
+const WKB_MULTILINESTRING: u32 = 5;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// EWKB high-bit flags on the geometry type code.
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// WKB framing variant, controlling the header written/expected before the
+/// standard WKB geometry-type-and-coordinates body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WkbDialect {
+    /// Plain ISO WKB: just byte order + geometry type + coordinates, no SRID.
+    Wkb,
+    /// PostGIS EWKB: byte order + geometry type (with Z/M/SRID-present high
+    /// bits) + optional SRID + coordinates.
+    Ewkb,
+    /// OGC GeoPackage binary: `"GP"` magic, version, flags byte (byte order,
+    /// envelope indicator, empty flag), SRID, optional envelope, then a
+    /// standard WKB body.
+    Geopackage,
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8> {
+    let mut buf = [0u8; 1];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    Ok(buf[0])
+}
+
+fn read_u32(reader: &mut impl Read, little_endian: bool) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    Ok(if little_endian {
+        u32::from_le_bytes(buf)
+    } else {
+        u32::from_be_bytes(buf)
+    })
+}
+
+fn read_i32(reader: &mut impl Read, little_endian: bool) -> Result<i32> {
+    Ok(read_u32(reader, little_endian)? as i32)
+}
+
+fn read_f64(reader: &mut impl Read, little_endian: bool) -> Result<f64> {
+    let mut buf = [0u8; 8];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    Ok(if little_endian {
+        f64::from_le_bytes(buf)
+    } else {
+        f64::from_be_bytes(buf)
+    })
+}
+
+/// Read a standard WKB (or EWKB, when `allow_ewkb_flags` is set) geometry
+/// body, including its own byte-order marker, dispatching shape callbacks
+/// into `processor`. Returns the SRID carried by this geometry's own EWKB
+/// flags, if any (nested SRIDs on sub-geometries of a collection are not
+/// bubbled up, matching how `processor.srid()` is only meaningful once).
+fn read_wkb_geom(
+    reader: &mut impl Read,
+    processor: &mut impl GeomProcessor,
+    allow_ewkb_flags: bool,
+    idx: usize,
+) -> Result<Option<i32>> {
+    let little_endian = read_u8(reader)? != 0;
+    let raw_type = read_u32(reader, little_endian)?;
+    let has_z = allow_ewkb_flags && raw_type & EWKB_Z != 0;
+    let has_m = allow_ewkb_flags && raw_type & EWKB_M != 0;
+    let has_srid = allow_ewkb_flags && raw_type & EWKB_SRID != 0;
+    let geom_type = raw_type & 0xff;
+    let srid = if has_srid {
+        let srid = read_i32(reader, little_endian)?;
+        processor.srid(Some(srid))?;
+        Some(srid)
+    } else {
+        None
+    };
+
+    let read_coord = |reader: &mut dyn Read, processor: &mut dyn GeomProcessor, idx: usize| -> Result<()> {
+        let x = read_f64(reader, little_endian)?;
+        let y = read_f64(reader, little_endian)?;
+        let z = if has_z {
+            Some(read_f64(reader, little_endian)?)
+        } else {
+            None
+        };
+        let m = if has_m {
+            Some(read_f64(reader, little_endian)?)
+        } else {
+            None
+        };
+        if has_z || has_m {
+            processor.coordinate(x, y, z, m, None, None, idx)
+        } else {
+            processor.xy(x, y, idx)
+        }
+    };
+
+    match geom_type {
+        WKB_POINT => {
+            processor.point_begin(idx)?;
+            read_coord(reader, processor, 0)?;
+            processor.point_end(idx)?;
+        }
+        WKB_LINESTRING => {
+            let n = read_u32(reader, little_endian)? as usize;
+            processor.linestring_begin(true, n, idx)?;
+            for i in 0..n {
+                read_coord(reader, processor, i)?;
+            }
+            processor.linestring_end(true, idx)?;
+        }
+        WKB_POLYGON => {
+            let nrings = read_u32(reader, little_endian)? as usize;
+            processor.polygon_begin(true, nrings, idx)?;
+            for ring_idx in 0..nrings {
+                let n = read_u32(reader, little_endian)? as usize;
+                processor.linestring_begin(false, n, ring_idx)?;
+                for i in 0..n {
+                    read_coord(reader, processor, i)?;
+                }
+                processor.linestring_end(false, ring_idx)?;
+            }
+            processor.polygon_end(true, idx)?;
+        }
+        WKB_MULTIPOINT => {
+            let n = read_u32(reader, little_endian)? as usize;
+            processor.multipoint_begin(n, idx)?;
+            for i in 0..n {
+                read_wkb_geom(reader, processor, allow_ewkb_flags, i)?;
+            }
+            processor.multipoint_end(idx)?;
+        }
+        WKB_MULTILINESTRING => {
+            let n = read_u32(reader, little_endian)? as usize;
+            processor.multilinestring_begin(n, idx)?;
+            for i in 0..n {
+                read_wkb_geom(reader, processor, allow_ewkb_flags, i)?;
+            }
+            processor.multilinestring_end(idx)?;
+        }
+        WKB_MULTIPOLYGON => {
+            let n = read_u32(reader, little_endian)? as usize;
+            processor.multipolygon_begin(n, idx)?;
+            for i in 0..n {
+                read_wkb_geom(reader, processor, allow_ewkb_flags, i)?;
+            }
+            processor.multipolygon_end(idx)?;
+        }
+        WKB_GEOMETRYCOLLECTION => {
+            let n = read_u32(reader, little_endian)? as usize;
+            for i in 0..n {
+                read_wkb_geom(reader, processor, allow_ewkb_flags, i)?;
+            }
+        }
+        _ => {
+            return Err(GeozeroError::Geometry(format!(
+                "unsupported WKB geometry type {geom_type}"
+            )))
+        }
+    }
+    Ok(srid)
+}
+
+/// Read a GeoPackage geometry BLOB (header + WKB body), dispatching shape
+/// callbacks into `processor` and returning the SRID from the GeoPackage
+/// header, if any. Callers that need the SRID alongside the decoded geometry
+/// (e.g. [`crate::geopackage`]'s `Decode` impls) read it from the return
+/// value instead of wrapping `processor` in an adapter.
+pub fn process_gpkg_geom(
+    reader: &mut impl Read,
+    processor: &mut impl GeomProcessor,
+) -> Result<Option<i32>> {
+    let magic = [read_u8(reader)?, read_u8(reader)?];
+    if &magic != b"GP" {
+        return Err(GeozeroError::Geometry("invalid GeoPackage magic".to_string()));
+    }
+    let _version = read_u8(reader)?;
+    let flags = read_u8(reader)?;
+    let little_endian = flags & 0x01 != 0;
+    let envelope_indicator = (flags >> 1) & 0x07;
+    // The empty flag is informational only: the WKB body that follows
+    // always encodes the real (possibly empty, e.g. an empty MultiPoint)
+    // geometry, so it must be parsed regardless of this bit.
+    let srid = read_i32(reader, little_endian)?;
+    let srid = if srid != 0 {
+        processor.srid(Some(srid))?;
+        Some(srid)
+    } else {
+        None
+    };
+    let envelope_doubles = match envelope_indicator {
+        0 => 0,
+        1 => 4,
+        2 | 3 => 6,
+        4 => 8,
+        _ => {
+            return Err(GeozeroError::Geometry(
+                "invalid GeoPackage envelope indicator".to_string(),
+            ))
+        }
+    };
+    for _ in 0..envelope_doubles {
+        read_f64(reader, little_endian)?;
+    }
+    read_wkb_geom(reader, processor, true, 0)?;
+    Ok(srid)
+}
+
+/// Read a PostGIS EWKB geometry (byte order + type/flags + optional SRID +
+/// coordinates), dispatching shape callbacks into `processor` and returning
+/// the SRID carried by the EWKB flags, if any.
+pub fn process_ewkb_geom(
+    reader: &mut impl Read,
+    processor: &mut impl GeomProcessor,
+) -> Result<Option<i32>> {
+    read_wkb_geom(reader, processor, true, 0)
+}
+
+fn write_coord(
+    out: &mut impl Write,
+    x: f64,
+    y: f64,
+    z: Option<f64>,
+    m: Option<f64>,
+) -> Result<()> {
+    out.write_all(&x.to_le_bytes())
+        .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    out.write_all(&y.to_le_bytes())
+        .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    if let Some(z) = z {
+        out.write_all(&z.to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    }
+    if let Some(m) = m {
+        out.write_all(&m.to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Writes geometries as WKB, EWKB or the GeoPackage WKB-based BLOB format,
+/// depending on [`WkbDialect`].
+pub struct WkbWriter<'a, W: Write> {
+    out: &'a mut W,
+    dialect: WkbDialect,
+    srid: Option<i32>,
+    header_written: bool,
+}
+
+impl<'a, W: Write> WkbWriter<'a, W> {
+    pub fn new(out: &'a mut W) -> Self {
+        Self::with_dialect(out, WkbDialect::Wkb)
+    }
+
+    pub fn with_dialect(out: &'a mut W, dialect: WkbDialect) -> Self {
+        WkbWriter {
+            out,
+            dialect,
+            srid: None,
+            header_written: false,
+        }
+    }
+
+    /// Set the SRID written in the EWKB/GeoPackage header. Has no effect for
+    /// [`WkbDialect::Wkb`].
+    pub fn with_srid(mut self, srid: i32) -> Self {
+        self.srid = Some(srid);
+        self
+    }
+
+    fn write_gpkg_header(&mut self, is_empty: bool) -> Result<()> {
+        self.out
+            .write_all(b"GP")
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+        self.out
+            .write_all(&[0u8])
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?; // version
+        let empty_flag = if is_empty { 0x10 } else { 0x00 };
+        // byte order bit set (little-endian), no envelope.
+        let flags = 0x01 | empty_flag;
+        self.out
+            .write_all(&[flags])
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+        self.out
+            .write_all(&self.srid.unwrap_or(0).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Ensure the dialect-specific header has been written, now that we know
+    /// whether the geometry is empty (called from the first shape callback).
+    fn ensure_header(&mut self, is_empty: bool) -> Result<()> {
+        if self.header_written {
+            return Ok(());
+        }
+        self.header_written = true;
+        if self.dialect == WkbDialect::Geopackage {
+            self.write_gpkg_header(is_empty)?;
+        }
+        Ok(())
+    }
+
+    fn write_byte_order(&mut self) -> Result<()> {
+        self.out
+            .write_all(&[1u8]) // little-endian
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn write_type(&mut self, geom_type: u32) -> Result<()> {
+        let mut raw_type = geom_type;
+        if self.dialect == WkbDialect::Ewkb && self.srid.is_some() {
+            raw_type |= EWKB_SRID;
+        }
+        self.out
+            .write_all(&raw_type.to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+        if self.dialect == WkbDialect::Ewkb {
+            if let Some(srid) = self.srid {
+                self.out
+                    .write_all(&srid.to_le_bytes())
+                    .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> GeomProcessor for WkbWriter<'_, W> {
+    fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+        write_coord(self.out, x, y, None, None)
+    }
+
+    fn empty_point(&mut self, _idx: usize) -> Result<()> {
+        self.ensure_header(true)?;
+        self.write_byte_order()?;
+        self.write_type(WKB_POINT)?;
+        write_coord(self.out, f64::NAN, f64::NAN, None, None)
+    }
+
+    fn point_begin(&mut self, _idx: usize) -> Result<()> {
+        self.ensure_header(false)?;
+        self.write_byte_order()?;
+        self.write_type(WKB_POINT)
+    }
+
+    fn point_end(&mut self, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn multipoint_begin(&mut self, size: usize, _idx: usize) -> Result<()> {
+        self.ensure_header(size == 0)?;
+        self.write_byte_order()?;
+        self.write_type(WKB_MULTIPOINT)?;
+        self.out
+            .write_all(&(size as u32).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn linestring_begin(&mut self, tagged: bool, size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.ensure_header(size == 0)?;
+            self.write_byte_order()?;
+            self.write_type(WKB_LINESTRING)?;
+        }
+        self.out
+            .write_all(&(size as u32).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn linestring_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn multilinestring_begin(&mut self, size: usize, _idx: usize) -> Result<()> {
+        self.ensure_header(size == 0)?;
+        self.write_byte_order()?;
+        self.write_type(WKB_MULTILINESTRING)?;
+        self.out
+            .write_all(&(size as u32).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn polygon_begin(&mut self, tagged: bool, size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.ensure_header(size == 0)?;
+            self.write_byte_order()?;
+            self.write_type(WKB_POLYGON)?;
+        }
+        self.out
+            .write_all(&(size as u32).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn multipolygon_begin(&mut self, size: usize, _idx: usize) -> Result<()> {
+        self.ensure_header(size == 0)?;
+        self.write_byte_order()?;
+        self.write_type(WKB_MULTIPOLYGON)?;
+        self.out
+            .write_all(&(size as u32).to_le_bytes())
+            .map_err(|e| GeozeroError::Geometry(e.to_string()))
+    }
+
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        Ok(())
+    }
+}
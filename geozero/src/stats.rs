@@ -0,0 +1,68 @@
+//! Opt-in instrumentation for conversion pipelines.
+use std::time::{Duration, Instant};
+
+/// Phase of a conversion pipeline that [`ConversionStats`] reports timing for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConversionPhase {
+    /// Everything between handing the processor to the source and getting
+    /// control back: dataset scanning and geometry/property processing are
+    /// interleaved by the source and can't be timed apart from outside it.
+    GeometryProcessing,
+    /// Encoding the output (e.g. assembling SVG/WKB bytes).
+    Encoding,
+}
+
+/// Timing and feature-count instrumentation for a single conversion run.
+///
+/// Modeled on resvg's `--perf` phase timing: entry points with a
+/// `_with_stats` variant (e.g. [`crate::ToSvg::to_svg_document_with_stats`])
+/// return one of these alongside the normal result. The plain entry points
+/// skip the phase timers, though [`crate::svg::SvgWriter`] still measures
+/// its own final encode step on every run.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionStats {
+    /// Number of features processed.
+    pub feature_count: u64,
+    durations: Vec<(ConversionPhase, Duration)>,
+}
+
+impl ConversionStats {
+    /// Elapsed wall-clock time spent in `phase`, if it was recorded.
+    pub fn duration(&self, phase: ConversionPhase) -> Option<Duration> {
+        self.durations
+            .iter()
+            .find(|(p, _)| *p == phase)
+            .map(|(_, d)| *d)
+    }
+
+    /// Sum of all recorded phase durations.
+    pub fn total(&self) -> Duration {
+        self.durations.iter().map(|(_, d)| d).sum()
+    }
+
+    /// Record an already-measured duration for `phase`, for callers (like
+    /// [`crate::svg::SvgWriter`]) that time their own work internally
+    /// instead of bracketing it with a [`PhaseTimer`].
+    pub(crate) fn record(&mut self, phase: ConversionPhase, elapsed: Duration) {
+        self.durations.push((phase, elapsed));
+    }
+}
+
+/// Measures a single phase and records it into a [`ConversionStats`] when dropped via [`PhaseTimer::stop`].
+pub(crate) struct PhaseTimer {
+    phase: ConversionPhase,
+    start: Instant,
+}
+
+impl PhaseTimer {
+    pub(crate) fn start(phase: ConversionPhase) -> Self {
+        PhaseTimer {
+            phase,
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn stop(self, stats: &mut ConversionStats) {
+        stats.record(self.phase, self.start.elapsed());
+    }
+}
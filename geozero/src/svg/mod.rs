@@ -6,6 +6,7 @@ pub use svg::*;
 pub(crate) mod conversion {
     use super::svg::*;
     use crate::error::Result;
+    use crate::stats::{ConversionPhase, ConversionStats, PhaseTimer};
     use crate::FeatureProcessor;
     use crate::{GeozeroDatasource, GeozeroDatasourceReader, GeozeroGeometry};
     use std::io::Read;
@@ -35,6 +36,8 @@ pub(crate) mod conversion {
         fn to_svg(&self) -> Result<String>;
         /// Convert to SVG document.
         fn to_svg_document(&self) -> Result<String>;
+        /// Convert to SVG document, also returning phase timing and feature count.
+        fn to_svg_document_with_stats(&self) -> Result<(String, ConversionStats)>;
     }
 
     impl<T: GeozeroGeometry> ToSvg for T {
@@ -42,14 +45,14 @@ pub(crate) mod conversion {
             let mut svg_data: Vec<u8> = Vec::new();
             let mut svg = SvgWriter::new(&mut svg_data, false);
             self.process_geom(&mut svg)?;
+            svg.finish()?;
             String::from_utf8(svg_data).map_err(|_| {
                 crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
             })
         }
         fn to_svg_document(&self) -> Result<String> {
             let mut svg_data: Vec<u8> = Vec::new();
-            let mut svg = SvgWriter::new(&mut svg_data, false);
-            // svg.set_dimensions(bbox.get(0), bbox.get(1), bbox.get(2), bbox.get(3), 800, 400);
+            let mut svg = SvgWriter::new(&mut svg_data, true);
             svg.dataset_begin(None)?;
             svg.feature_begin(0)?;
             self.process_geom(&mut svg)?;
@@ -59,39 +62,110 @@ pub(crate) mod conversion {
                 crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
             })
         }
+        fn to_svg_document_with_stats(&self) -> Result<(String, ConversionStats)> {
+            let mut stats = ConversionStats::default();
+            let mut svg_data: Vec<u8> = Vec::new();
+            let mut svg = SvgWriter::new(&mut svg_data, true);
+            svg.dataset_begin(None)?;
+            svg.feature_begin(0)?;
+            let timer = PhaseTimer::start(ConversionPhase::GeometryProcessing);
+            self.process_geom(&mut svg)?;
+            svg.feature_end(0)?;
+            timer.stop(&mut stats);
+            stats.feature_count = svg.feature_count();
+            svg.dataset_end()?;
+            if let Some(encode_duration) = svg.encode_duration() {
+                stats.record(ConversionPhase::Encoding, encode_duration);
+            }
+            let out = String::from_utf8(svg_data).map_err(|_| {
+                crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
+            })?;
+            Ok((out, stats))
+        }
     }
 
     /// Consume features as SVG.
     pub trait ProcessToSvg {
         /// Consume features as SVG String.
         fn to_svg(&mut self) -> Result<String>;
+        /// Consume features as SVG String, also returning phase timing and feature count.
+        fn to_svg_with_stats(&mut self) -> Result<(String, ConversionStats)>;
     }
 
     impl<T: GeozeroDatasource> ProcessToSvg for T {
         fn to_svg(&mut self) -> Result<String> {
             let mut svg_data: Vec<u8> = Vec::new();
-            let mut svg = SvgWriter::new(&mut svg_data, false);
+            let mut svg = SvgWriter::new(&mut svg_data, true);
             self.process(&mut svg)?;
             String::from_utf8(svg_data).map_err(|_| {
                 crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
             })
         }
+        fn to_svg_with_stats(&mut self) -> Result<(String, ConversionStats)> {
+            let mut stats = ConversionStats::default();
+            let mut svg_data: Vec<u8> = Vec::new();
+            let mut svg = SvgWriter::new(&mut svg_data, true);
+            // `process` drives the source's own scan/decode loop and calls
+            // back into `svg` for geometry/property processing *and*, via
+            // `dataset_end`, the final SVG serialization - those can't be
+            // told apart from out here. Time the whole call, then carve the
+            // accurately self-timed encode step back out of it.
+            let start = std::time::Instant::now();
+            self.process(&mut svg)?;
+            let elapsed = start.elapsed();
+            stats.feature_count = svg.feature_count();
+            let encode_duration = svg.encode_duration().unwrap_or_default();
+            stats.record(
+                ConversionPhase::GeometryProcessing,
+                elapsed.saturating_sub(encode_duration),
+            );
+            stats.record(ConversionPhase::Encoding, encode_duration);
+            let out = String::from_utf8(svg_data).map_err(|_| {
+                crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
+            })?;
+            Ok((out, stats))
+        }
     }
 
     /// Read features as SVG.
     pub trait ReadAsSvg {
         /// Consume features as SVG String.
         fn read_as_svg<R: Read>(reader: R) -> Result<String>;
+        /// Consume features as SVG String, also returning phase timing and feature count.
+        fn read_as_svg_with_stats<R: Read>(reader: R) -> Result<(String, ConversionStats)>;
     }
 
     impl<T: GeozeroDatasourceReader> ReadAsSvg for T {
         fn read_as_svg<R: Read>(reader: R) -> Result<String> {
             let mut svg_data: Vec<u8> = Vec::new();
-            let mut svg = SvgWriter::new(&mut svg_data, false);
+            let mut svg = SvgWriter::new(&mut svg_data, true);
             T::read(reader, &mut svg)?;
             String::from_utf8(svg_data).map_err(|_| {
                 crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
             })
         }
+        fn read_as_svg_with_stats<R: Read>(reader: R) -> Result<(String, ConversionStats)> {
+            let mut stats = ConversionStats::default();
+            let mut svg_data: Vec<u8> = Vec::new();
+            let mut svg = SvgWriter::new(&mut svg_data, true);
+            // Same reasoning as `ProcessToSvg::to_svg_with_stats`: `T::read`
+            // interleaves scanning with geometry/property processing and the
+            // final serialization, so only the self-timed encode step can be
+            // split back out of the total.
+            let start = std::time::Instant::now();
+            T::read(reader, &mut svg)?;
+            let elapsed = start.elapsed();
+            stats.feature_count = svg.feature_count();
+            let encode_duration = svg.encode_duration().unwrap_or_default();
+            stats.record(
+                ConversionPhase::GeometryProcessing,
+                elapsed.saturating_sub(encode_duration),
+            );
+            stats.record(ConversionPhase::Encoding, encode_duration);
+            let out = String::from_utf8(svg_data).map_err(|_| {
+                crate::error::GeozeroError::Geometry("Invalid UTF-8 encoding".to_string())
+            })?;
+            Ok((out, stats))
+        }
     }
 }
@@ -0,0 +1,659 @@
+use crate::error::Result;
+use crate::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::io::Write;
+
+/// Axis-aligned bounding box in geometry (not pixel) coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bbox {
+    pub minx: f64,
+    pub miny: f64,
+    pub maxx: f64,
+    pub maxy: f64,
+}
+
+impl Bbox {
+    fn empty() -> Self {
+        Bbox {
+            minx: f64::INFINITY,
+            miny: f64::INFINITY,
+            maxx: f64::NEG_INFINITY,
+            maxy: f64::NEG_INFINITY,
+        }
+    }
+
+    fn expand(&mut self, x: f64, y: f64) {
+        self.minx = self.minx.min(x);
+        self.miny = self.miny.min(y);
+        self.maxx = self.maxx.max(x);
+        self.maxy = self.maxy.max(y);
+    }
+
+    fn is_valid(&self) -> bool {
+        self.minx <= self.maxx && self.miny <= self.maxy
+    }
+
+    fn width(&self) -> f64 {
+        self.maxx - self.minx
+    }
+
+    fn height(&self) -> f64 {
+        self.maxy - self.miny
+    }
+}
+
+/// Presentation attributes applied to a feature's SVG path element.
+///
+/// Fields left as `None` are omitted, so the element falls back to the
+/// document's (or browser's) default styling.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SvgStyle {
+    pub stroke: Option<String>,
+    pub fill: Option<String>,
+    pub stroke_width: Option<f64>,
+    pub opacity: Option<f64>,
+    pub class: Option<String>,
+    pub id: Option<String>,
+}
+
+/// Escape characters that would otherwise break out of a double-quoted XML
+/// attribute value, since [`SvgStyle`] fields are commonly derived from
+/// arbitrary feature properties.
+fn escape_attr(value: &str) -> std::borrow::Cow<'_, str> {
+    if !value.contains(['&', '<', '"']) {
+        return std::borrow::Cow::Borrowed(value);
+    }
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    std::borrow::Cow::Owned(escaped)
+}
+
+impl SvgStyle {
+    /// Overlay `other` on top of `self`, keeping `self`'s values for fields
+    /// `other` leaves unset.
+    fn merge(&mut self, other: SvgStyle) {
+        if other.stroke.is_some() {
+            self.stroke = other.stroke;
+        }
+        if other.fill.is_some() {
+            self.fill = other.fill;
+        }
+        if other.stroke_width.is_some() {
+            self.stroke_width = other.stroke_width;
+        }
+        if other.opacity.is_some() {
+            self.opacity = other.opacity;
+        }
+        if other.class.is_some() {
+            self.class = other.class;
+        }
+        if other.id.is_some() {
+            self.id = other.id;
+        }
+    }
+
+    fn write_attrs<W: Write>(&self, out: &mut W) -> Result<()> {
+        if let Some(id) = &self.id {
+            write!(out, r#" id="{}""#, escape_attr(id))?;
+        }
+        if let Some(class) = &self.class {
+            write!(out, r#" class="{}""#, escape_attr(class))?;
+        }
+        if let Some(stroke) = &self.stroke {
+            write!(out, r#" stroke="{}""#, escape_attr(stroke))?;
+        }
+        if let Some(fill) = &self.fill {
+            write!(out, r#" fill="{}""#, escape_attr(fill))?;
+        }
+        if let Some(stroke_width) = self.stroke_width {
+            write!(out, r#" stroke-width="{stroke_width}""#)?;
+        }
+        if let Some(opacity) = self.opacity {
+            write!(out, r#" opacity="{opacity}""#)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps a feature property to a style override, called once per property
+/// value as it is processed (see [`SvgWriter::set_style_fn`]).
+pub type StyleFn<'a> = dyn Fn(usize, &str, &ColumnValue) -> Option<SvgStyle> + 'a;
+
+/// Generator for SVG geometry
+pub struct SvgWriter<'a, W: Write> {
+    out: &'a mut W,
+    /// Buffer holding the SVG body, so the header (which needs the final
+    /// viewBox) can be written once the geometry has been fully processed.
+    body: Vec<u8>,
+    /// Invert y-axis, so that the geometry is rendered as in a mathematical
+    /// coordinate system (y growing upwards) instead of the SVG default.
+    invert_y: bool,
+    /// Explicit bounding box set through [`SvgWriter::set_dimensions`].
+    /// When absent, the bbox is computed automatically from the processed
+    /// geometry.
+    explicit_bbox: Option<Bbox>,
+    /// Target pixel width/height, used together with `explicit_bbox`.
+    dimensions: Option<(u32, u32)>,
+    /// Bounding box accumulated while processing geometries.
+    auto_bbox: Bbox,
+    path_open: bool,
+    /// Style applied when no `style_fn` is set, or as the base a `style_fn`
+    /// overrides for the current feature.
+    default_style: SvgStyle,
+    /// Maps feature properties to style overrides.
+    style_fn: Option<Box<StyleFn<'a>>>,
+    /// Style resolved for the feature currently being written.
+    current_style: SvgStyle,
+    /// Points collected for the circular string currently being processed,
+    /// buffered so arcs can be derived from consecutive point triples once
+    /// all of them are known.
+    curve_points: Option<Vec<(f64, f64)>>,
+    /// Number of features seen via [`SvgWriter::feature_begin`], exposed for
+    /// callers building a [`crate::stats::ConversionStats`].
+    feature_count: u64,
+    /// Wall-clock time spent in [`SvgWriter::dataset_end`]/[`SvgWriter::finish`]
+    /// assembling and writing the final SVG bytes, set on whichever of the
+    /// two last ran. Exposed for callers building a
+    /// [`crate::stats::ConversionStats`], since that work happens inside
+    /// this writer and can't be timed accurately from the outside.
+    encode_duration: Option<std::time::Duration>,
+}
+
+/// An arc whose cross product (P1-P0) x (P2-P0) is smaller in magnitude than
+/// this is treated as collinear and drawn as a straight line, to avoid
+/// division by (near) zero when solving for its center.
+const ARC_EPSILON: f64 = 1e-9;
+
+impl<'a, W: Write> SvgWriter<'a, W> {
+    pub fn new(out: &'a mut W, invert_y: bool) -> SvgWriter<'a, W> {
+        SvgWriter {
+            out,
+            body: Vec::new(),
+            invert_y,
+            explicit_bbox: None,
+            dimensions: None,
+            auto_bbox: Bbox::empty(),
+            path_open: false,
+            default_style: SvgStyle::default(),
+            style_fn: None,
+            current_style: SvgStyle::default(),
+            curve_points: None,
+            feature_count: 0,
+            encode_duration: None,
+        }
+    }
+
+    /// Number of features processed so far.
+    pub fn feature_count(&self) -> u64 {
+        self.feature_count
+    }
+
+    /// Wall-clock time spent assembling/writing the final SVG bytes in the
+    /// most recent [`SvgWriter::dataset_end`] or [`SvgWriter::finish`] call,
+    /// if either has run yet.
+    pub fn encode_duration(&self) -> Option<std::time::Duration> {
+        self.encode_duration
+    }
+
+    /// Set the style applied to every feature that has no overrides from a
+    /// [`StyleFn`] set with [`SvgWriter::set_style_fn`].
+    pub fn set_default_style(&mut self, style: SvgStyle) {
+        self.default_style = style;
+    }
+
+    /// Derive per-feature styling from feature properties. Called once per
+    /// property as it is processed; returning `Some(style)` overlays it on
+    /// top of the feature's style so far.
+    pub fn set_style_fn<F>(&mut self, style_fn: F)
+    where
+        F: Fn(usize, &str, &ColumnValue) -> Option<SvgStyle> + 'a,
+    {
+        self.style_fn = Some(Box::new(style_fn));
+    }
+
+    /// Set an explicit bounding box and target pixel size, instead of
+    /// fitting the viewBox to the processed geometry automatically.
+    pub fn set_dimensions(
+        &mut self,
+        minx: f64,
+        miny: f64,
+        maxx: f64,
+        maxy: f64,
+        width: u32,
+        height: u32,
+    ) {
+        self.explicit_bbox = Some(Bbox {
+            minx,
+            miny,
+            maxx,
+            maxy,
+        });
+        self.dimensions = Some((width, height));
+    }
+
+    fn bbox(&self) -> Bbox {
+        self.explicit_bbox.unwrap_or(self.auto_bbox)
+    }
+
+    pub fn dataset_begin(&mut self, _name: Option<&str>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn dataset_end(&mut self) -> Result<()> {
+        let start = std::time::Instant::now();
+        let bbox = self.bbox();
+        let (minx, miny, width, height) = if bbox.is_valid() {
+            (bbox.minx, bbox.miny, bbox.width().max(0.0), bbox.height().max(0.0))
+        } else {
+            (0.0, 0.0, 0.0, 0.0)
+        };
+        write!(
+            self.out,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink""#
+        )?;
+        if let Some((w, h)) = self.dimensions {
+            write!(self.out, r#" width="{w}" height="{h}""#)?;
+        }
+        write!(self.out, r#" viewBox="{minx} {miny} {width} {height}">"#)?;
+        if self.invert_y {
+            let flip_y = miny + miny + height;
+            write!(
+                self.out,
+                r#"<g transform="matrix(1 0 0 -1 0 {flip_y})">"#
+            )?;
+        }
+        self.out.write_all(&self.body)?;
+        if self.invert_y {
+            write!(self.out, "</g>")?;
+        }
+        write!(self.out, "</svg>")?;
+        self.encode_duration = Some(start.elapsed());
+        Ok(())
+    }
+
+    /// Flush the buffered body straight to `out`, without a document header
+    /// or the y-flip transform. For callers that process a single geometry
+    /// as a bare SVG fragment (no [`SvgWriter::dataset_begin`]/
+    /// [`SvgWriter::dataset_end`] wrapper to flush it for them).
+    pub fn finish(&mut self) -> Result<()> {
+        let start = std::time::Instant::now();
+        self.out.write_all(&self.body)?;
+        self.body.clear();
+        self.encode_duration = Some(start.elapsed());
+        Ok(())
+    }
+
+    pub fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.feature_count += 1;
+        self.current_style = self.default_style.clone();
+        Ok(())
+    }
+
+    pub fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn start_path(&mut self) -> Result<()> {
+        if !self.path_open {
+            write!(self.body, r#"<path d=""#)?;
+            self.path_open = true;
+        }
+        Ok(())
+    }
+
+    fn end_path(&mut self) -> Result<()> {
+        if self.path_open {
+            write!(self.body, r#"""#)?;
+            self.current_style.write_attrs(&mut self.body)?;
+            write!(self.body, "/>")?;
+            self.path_open = false;
+        }
+        Ok(())
+    }
+
+    /// Emit a circular string (SQL-MM Part 3) as a sequence of SVG arc (and,
+    /// where the control points are collinear, straight line) commands.
+    ///
+    /// Points come in overlapping triples `(P0, P1, P2)`, `(P2, P3, P4)`, ...
+    /// each describing one arc segment from its first to its last point,
+    /// passing through the middle one.
+    fn write_circular_arcs(&mut self, points: &[(f64, f64)]) -> Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        let (x0, y0) = points[0];
+        write!(self.body, "M{x0} {y0} ")?;
+        for triple in points.windows(3).step_by(2) {
+            let (p0, p1, p2) = (triple[0], triple[1], triple[2]);
+            self.write_arc_segment(p0, p1, p2)?;
+        }
+        Ok(())
+    }
+
+    fn write_arc_segment(
+        &mut self,
+        p0: (f64, f64),
+        p1: (f64, f64),
+        p2: (f64, f64),
+    ) -> Result<()> {
+        let (x0, y0) = p0;
+        let (x1, y1) = p1;
+        let (x2, y2) = p2;
+
+        // (P1-P0) x (P2-P0): sign gives the arc's sweep direction, magnitude
+        // (near) zero means the three points are collinear.
+        let cross = (x1 - x0) * (y2 - y0) - (y1 - y0) * (x2 - x0);
+        if cross.abs() < ARC_EPSILON {
+            write!(self.body, "L{x2} {y2} ")?;
+            return Ok(());
+        }
+
+        // Center is the intersection of the perpendicular bisectors of
+        // P0P1 and P1P2, from |C-P0|^2 = |C-P1|^2 = |C-P2|^2.
+        let a1 = x1 - x0;
+        let b1 = y1 - y0;
+        let c1 = (x1 * x1 + y1 * y1 - x0 * x0 - y0 * y0) / 2.0;
+        let a2 = x2 - x1;
+        let b2 = y2 - y1;
+        let c2 = (x2 * x2 + y2 * y2 - x1 * x1 - y1 * y1) / 2.0;
+        let det = a1 * b2 - a2 * b1;
+        let cx = (c1 * b2 - c2 * b1) / det;
+        let cy = (a1 * c2 - a2 * c1) / det;
+        let radius = ((x0 - cx).powi(2) + (y0 - cy).powi(2)).sqrt();
+
+        let sweep = if cross > 0.0 { 1 } else { 0 };
+        let angle0 = (y0 - cy).atan2(x0 - cx);
+        let angle2 = (y2 - cy).atan2(x2 - cx);
+        let mut swept = angle2 - angle0;
+        if sweep == 1 {
+            if swept < 0.0 {
+                swept += std::f64::consts::TAU;
+            }
+        } else if swept > 0.0 {
+            swept -= std::f64::consts::TAU;
+        }
+        let large_arc = if swept.abs() > std::f64::consts::PI {
+            1
+        } else {
+            0
+        };
+
+        write!(self.body, "A{radius} {radius} 0 {large_arc} {sweep} {x2} {y2} ")?;
+        Ok(())
+    }
+}
+
+impl<W: Write> GeomProcessor for SvgWriter<'_, W> {
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.auto_bbox.expand(x, y);
+        if let Some(curve_points) = &mut self.curve_points {
+            curve_points.push((x, y));
+            return Ok(());
+        }
+        if idx == 0 {
+            write!(self.body, "M{x} {y} ")?;
+        } else {
+            write!(self.body, "L{x} {y} ")?;
+        }
+        Ok(())
+    }
+
+    fn point_begin(&mut self, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn point_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+
+    fn multipoint_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+
+    fn linestring_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.start_path()?;
+        }
+        Ok(())
+    }
+
+    fn linestring_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        if tagged {
+            self.end_path()?;
+        }
+        Ok(())
+    }
+
+    fn multilinestring_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+
+    fn polygon_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.start_path()?;
+        }
+        Ok(())
+    }
+
+    fn polygon_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        write!(self.body, "Z ")?;
+        if tagged {
+            self.end_path()?;
+        }
+        Ok(())
+    }
+
+    fn multipolygon_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+
+    fn circularstring_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.start_path()?;
+        }
+        self.curve_points = Some(Vec::new());
+        Ok(())
+    }
+
+    fn circularstring_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        if let Some(curve_points) = self.curve_points.take() {
+            self.write_circular_arcs(&curve_points)?;
+        }
+        if tagged {
+            self.end_path()?;
+        }
+        Ok(())
+    }
+
+    fn compoundcurve_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.start_path()?;
+        }
+        Ok(())
+    }
+
+    fn compoundcurve_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        if tagged {
+            self.end_path()?;
+        }
+        Ok(())
+    }
+
+    fn curvepolygon_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        if tagged {
+            self.start_path()?;
+        }
+        Ok(())
+    }
+
+    fn curvepolygon_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        write!(self.body, "Z ")?;
+        if tagged {
+            self.end_path()?;
+        }
+        Ok(())
+    }
+
+    fn multicurve_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn multicurve_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+
+    fn multisurface_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.start_path()
+    }
+
+    fn multisurface_end(&mut self, _idx: usize) -> Result<()> {
+        self.end_path()
+    }
+}
+
+impl<'a, W: Write> PropertyProcessor for SvgWriter<'a, W> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if let Some(style_fn) = &self.style_fn {
+            if let Some(style) = style_fn(idx, name, value) {
+                self.current_style.merge(style);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl<W: Write> FeatureProcessor for SvgWriter<'_, W> {
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        SvgWriter::dataset_begin(self, name)
+    }
+
+    fn dataset_end(&mut self) -> Result<()> {
+        SvgWriter::dataset_end(self)
+    }
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        SvgWriter::feature_begin(self, idx)
+    }
+
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        SvgWriter::feature_end(self, idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dataset_end_writes_viewbox_from_processed_geometry() {
+        let mut out: Vec<u8> = Vec::new();
+        let mut svg = SvgWriter::new(&mut out, false);
+        svg.dataset_begin(None).unwrap();
+        svg.feature_begin(0).unwrap();
+        svg.point_begin(0).unwrap();
+        svg.xy(0.0, 0.0, 0).unwrap();
+        svg.point_end(0).unwrap();
+        svg.feature_end(0).unwrap();
+        svg.feature_begin(1).unwrap();
+        svg.point_begin(0).unwrap();
+        svg.xy(10.0, 20.0, 0).unwrap();
+        svg.point_end(0).unwrap();
+        svg.feature_end(1).unwrap();
+        svg.dataset_end().unwrap();
+        let doc = String::from_utf8(out).unwrap();
+        assert!(doc.contains(r#"viewBox="0 0 10 20">"#));
+        assert!(!doc.contains("transform"));
+    }
+
+    #[test]
+    fn dataset_end_flips_y_around_the_viewbox_center() {
+        let mut out: Vec<u8> = Vec::new();
+        let mut svg = SvgWriter::new(&mut out, true);
+        svg.dataset_begin(None).unwrap();
+        svg.feature_begin(0).unwrap();
+        svg.point_begin(0).unwrap();
+        svg.xy(0.0, 0.0, 0).unwrap();
+        svg.point_end(0).unwrap();
+        svg.feature_end(0).unwrap();
+        svg.feature_begin(1).unwrap();
+        svg.point_begin(0).unwrap();
+        svg.xy(10.0, 20.0, 0).unwrap();
+        svg.point_end(0).unwrap();
+        svg.feature_end(1).unwrap();
+        svg.dataset_end().unwrap();
+        let doc = String::from_utf8(out).unwrap();
+        assert!(doc.contains(r#"viewBox="0 0 10 20">"#));
+        assert!(doc.contains(r#"<g transform="matrix(1 0 0 -1 0 20)">"#));
+    }
+
+    #[test]
+    fn escape_attr_escapes_xml_metacharacters() {
+        assert_eq!(escape_attr("plain"), "plain");
+        assert_eq!(escape_attr(r#"a&b<c"d"#), "a&amp;b&lt;c&quot;d");
+    }
+
+    #[test]
+    fn write_attrs_escapes_a_style_value_derived_from_feature_properties() {
+        let style = SvgStyle {
+            class: Some(r#""><script>alert(1)</script>"#.to_string()),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        style.write_attrs(&mut out).unwrap();
+        let attrs = String::from_utf8(out).unwrap();
+        assert!(!attrs.contains("<script>"));
+        assert!(attrs.contains(r#"class="&quot;>&lt;script>alert(1)&lt;/script>""#));
+    }
+
+    #[test]
+    fn write_arc_segment_collinear_points_fall_back_to_a_line() {
+        let mut out: Vec<u8> = Vec::new();
+        let mut svg = SvgWriter::new(&mut out, false);
+        svg.write_arc_segment((0.0, 0.0), (1.0, 1.0), (2.0, 2.0))
+            .unwrap();
+        assert_eq!(svg.body, b"L2 2 ");
+    }
+
+    #[test]
+    fn write_arc_segment_half_circle_ccw_is_not_a_large_arc() {
+        // Unit circle centered on the origin: P0 at 0 deg, P1 (the
+        // pass-through point) at 90 deg, P2 at 180 deg - a 180 deg
+        // counter-clockwise sweep, exactly pi and so not past the
+        // large-arc threshold.
+        let mut out: Vec<u8> = Vec::new();
+        let mut svg = SvgWriter::new(&mut out, false);
+        svg.write_arc_segment((1.0, 0.0), (0.0, 1.0), (-1.0, 0.0))
+            .unwrap();
+        assert_eq!(svg.body, b"A1 1 0 0 1 -1 0 ");
+    }
+
+    #[test]
+    fn write_arc_segment_sets_large_arc_flag_past_half_circle() {
+        // Same unit circle, but P1 is on the opposite side from the short
+        // way between P0 and P2, so the arc through it sweeps 270 deg.
+        let mut out: Vec<u8> = Vec::new();
+        let mut svg = SvgWriter::new(&mut out, false);
+        svg.write_arc_segment((1.0, 0.0), (-1.0, 0.0), (0.0, 1.0))
+            .unwrap();
+        assert_eq!(svg.body, b"A1 1 0 1 0 0 1 ");
+    }
+}